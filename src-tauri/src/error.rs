@@ -0,0 +1,122 @@
+use mongodb::bson::oid::ObjectId;
+use serde::ser::SerializeStruct;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Crate-wide error type for everything that crosses a `#[tauri::command]`
+/// boundary (Tauri requires command errors to be `Serialize`, so this
+/// replaces the old `Result<_, String>` convention). Each variant carries a
+/// stable `code()` the frontend can branch on without parsing `message`.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("database error: {0}")]
+    Db(#[from] mongodb::error::Error),
+
+    #[error("invalid MongoDB URI: {0}")]
+    InvalidUri(String),
+
+    #[error("rez command `{command}` failed: {stderr}")]
+    RezCommand {
+        command: String,
+        stderr: String,
+        /// Path to the full captured stdout/stderr log, when one was
+        /// written (see `logged_command::LoggedCommand`).
+        log_path: Option<PathBuf>,
+    },
+
+    #[error("failed to generate RXT file: {0}")]
+    RxtGeneration(String),
+
+    #[error("stage {0} not found")]
+    StageNotFound(ObjectId),
+
+    #[error("invalid object id: {0}")]
+    InvalidObjectId(String),
+
+    #[error("package collection {version} not found for URI {uri}")]
+    PackageCollectionNotFound { version: String, uri: String },
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("{0}")]
+    Other(String),
+}
+
+impl Error {
+    /// Stable diagnostic code the UI can switch on (e.g. to offer a
+    /// "reconfigure MongoDB" action for `rezlauncher::db`).
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::Db(_) => "rezlauncher::db",
+            Error::InvalidUri(_) => "rezlauncher::invalid_uri",
+            Error::RezCommand { .. } => "rezlauncher::rez",
+            Error::RxtGeneration(_) => "rezlauncher::rez",
+            Error::StageNotFound(_) => "rezlauncher::stage_not_found",
+            Error::InvalidObjectId(_) => "rezlauncher::invalid_object_id",
+            Error::PackageCollectionNotFound { .. } => "rezlauncher::package_collection_not_found",
+            Error::Io(_) => "rezlauncher::io",
+            Error::Other(_) => "rezlauncher::other",
+        }
+    }
+
+    fn details(&self) -> Option<String> {
+        match self {
+            Error::RezCommand { command, log_path, .. } => {
+                let mut detail = format!("command: {}", command);
+                if let Some(path) = log_path {
+                    detail.push_str(&format!("; log: {}", path.display()));
+                }
+                Some(detail)
+            }
+            Error::StageNotFound(id) => Some(id.to_hex()),
+            Error::PackageCollectionNotFound { version, uri } => {
+                Some(format!("version={}, uri={}", version, uri))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl From<String> for Error {
+    fn from(message: String) -> Self {
+        Error::Other(message)
+    }
+}
+
+/// Emits `{ code, message, details }` so the frontend can branch on `code`
+/// without string-matching `message`.
+impl Serialize for Error {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut state = serializer.serialize_struct("Error", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("details", &self.details())?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_to_the_code_message_details_shape() {
+        let id = ObjectId::new();
+        let value = serde_json::to_value(Error::StageNotFound(id)).unwrap();
+        assert_eq!(value["code"], "rezlauncher::stage_not_found");
+        assert_eq!(value["message"], format!("stage {} not found", id));
+        assert_eq!(value["details"], id.to_hex());
+    }
+
+    #[test]
+    fn serializes_null_details_when_the_variant_carries_none() {
+        let value = serde_json::to_value(Error::Other("boom".to_string())).unwrap();
+        assert_eq!(value["code"], "rezlauncher::other");
+        assert_eq!(value["message"], "boom");
+        assert!(value["details"].is_null());
+    }
+}
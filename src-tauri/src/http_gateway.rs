@@ -0,0 +1,268 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use mongodb::bson::oid::ObjectId;
+use serde::Deserialize;
+
+use crate::error::Error;
+use crate::repository::{DbRepository, Stage};
+use crate::{log_message, LogState};
+
+#[derive(Clone)]
+struct GatewayState {
+    db_repo: Arc<dyn DbRepository>,
+    log_state: Arc<LogState>,
+}
+
+#[derive(Deserialize)]
+struct StagesQuery {
+    uri: String,
+    active_only: Option<bool>,
+}
+
+/// HTTP responses mirror the `{ code, message, details }` shape of
+/// `Error::serialize`, so a farm node gets the same diagnostic a Tauri
+/// command caller would.
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            Error::StageNotFound(_) => StatusCode::NOT_FOUND,
+            Error::PackageCollectionNotFound { .. } => StatusCode::NOT_FOUND,
+            Error::InvalidObjectId(_) => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, Json(self)).into_response()
+    }
+}
+
+fn parse_stage_id(id: &str) -> Result<ObjectId, Error> {
+    ObjectId::parse_str(id).map_err(|_| Error::InvalidObjectId(id.to_string()))
+}
+
+async fn list_stages(
+    State(state): State<GatewayState>,
+    Query(query): Query<StagesQuery>,
+) -> Result<Json<Vec<Stage>>, Error> {
+    log_message(&state.log_state, format!("HTTP gateway: GET /stages?uri={}&active_only={:?}", query.uri, query.active_only));
+    let stages = state.db_repo.find_stages_by_uri(&query.uri, query.active_only).await?;
+    Ok(Json(stages))
+}
+
+async fn get_stage(
+    State(state): State<GatewayState>,
+    Path(id): Path<String>,
+) -> Result<Json<Stage>, Error> {
+    log_message(&state.log_state, format!("HTTP gateway: GET /stages/{}", id));
+    let object_id = parse_stage_id(&id)?;
+    let stage = state.db_repo.find_stage_by_id(object_id).await?.ok_or(Error::StageNotFound(object_id))?;
+    Ok(Json(stage))
+}
+
+async fn get_stage_rxt(
+    State(state): State<GatewayState>,
+    Path(id): Path<String>,
+) -> Result<String, Error> {
+    log_message(&state.log_state, format!("HTTP gateway: GET /stages/{}/rxt", id));
+    let object_id = parse_stage_id(&id)?;
+    let stage = state.db_repo.find_stage_by_id(object_id).await?.ok_or(Error::StageNotFound(object_id))?;
+    Ok(stage.rxt)
+}
+
+/// Starts the embedded read-only HTTP gateway so render-farm nodes and DCC
+/// plugins can pull the active stage RXT with a plain `curl`, instead of
+/// going through a Tauri command only the desktop UI can call. Disabled
+/// unless the caller opts in with a bind address (see `main.rs`).
+pub async fn serve(bind_addr: SocketAddr, db_repo: Arc<dyn DbRepository>, log_state: LogState) -> Result<(), Error> {
+    let log_state = Arc::new(log_state);
+    log_message(&log_state, format!("Starting HTTP gateway on {}", bind_addr));
+
+    let state = GatewayState { db_repo, log_state: Arc::clone(&log_state) };
+    let app = Router::new()
+        .route("/stages", get(list_stages))
+        .route("/stages/:id", get(get_stage))
+        .route("/stages/:id/rxt", get(get_stage_rxt))
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(bind_addr).await?;
+    axum::serve(listener, app)
+        .await
+        .map_err(|e| Error::Other(format!("HTTP gateway stopped: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::MockDbRepository;
+    use axum::body::Body;
+    use axum::http::Request;
+    use http_body_util::BodyExt;
+    use std::fs::OpenOptions;
+    use tower::ServiceExt;
+
+    fn test_log_state() -> LogState {
+        let path = std::env::temp_dir().join(format!(
+            "rezlauncher_http_gateway_test_{}.log",
+            ObjectId::new().to_hex()
+        ));
+        let file = OpenOptions::new().create(true).append(true).open(path).unwrap();
+        LogState(std::sync::Mutex::new(file))
+    }
+
+    fn router(db_repo: MockDbRepository) -> Router {
+        let state = GatewayState {
+            db_repo: Arc::new(db_repo),
+            log_state: Arc::new(test_log_state()),
+        };
+        Router::new()
+            .route("/stages", get(list_stages))
+            .route("/stages/:id", get(get_stage))
+            .route("/stages/:id/rxt", get(get_stage_rxt))
+            .with_state(state)
+    }
+
+    fn dummy_stage(id: ObjectId) -> Stage {
+        Stage {
+            id: Some(id),
+            name: "dev".to_string(),
+            uri: "show/shot".to_string(),
+            from_version: "1.0".to_string(),
+            rxt: "!REZ_STAGE\n".to_string(),
+            tools: vec!["maya".to_string()],
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            created_by: "test_user".to_string(),
+            active: true,
+        }
+    }
+
+    async fn body_bytes(response: Response) -> Vec<u8> {
+        response.into_body().collect().await.unwrap().to_bytes().to_vec()
+    }
+
+    #[tokio::test]
+    async fn get_stage_returns_404_when_not_found() {
+        let id = ObjectId::new();
+        let mut mock_repo = MockDbRepository::new();
+        mock_repo
+            .expect_find_stage_by_id()
+            .with(mockall::predicate::eq(id))
+            .times(1)
+            .returning(|_| Ok(None));
+
+        let response = router(mock_repo)
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/stages/{}", id.to_hex()))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let body = body_bytes(response).await;
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["code"], "rezlauncher::stage_not_found");
+    }
+
+    #[tokio::test]
+    async fn get_stage_returns_400_on_a_malformed_id() {
+        let mock_repo = MockDbRepository::new();
+
+        let response = router(mock_repo)
+            .oneshot(
+                Request::builder()
+                    .uri("/stages/not-an-object-id")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = body_bytes(response).await;
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["code"], "rezlauncher::invalid_object_id");
+    }
+
+    #[tokio::test]
+    async fn get_stage_rxt_returns_the_raw_rxt_text() {
+        let id = ObjectId::new();
+        let stage = dummy_stage(id);
+        let expected_rxt = stage.rxt.clone();
+        let mut mock_repo = MockDbRepository::new();
+        mock_repo
+            .expect_find_stage_by_id()
+            .with(mockall::predicate::eq(id))
+            .times(1)
+            .returning(move |_| Ok(Some(stage.clone())));
+
+        let response = router(mock_repo)
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/stages/{}/rxt", id.to_hex()))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_bytes(response).await;
+        assert_eq!(String::from_utf8(body).unwrap(), expected_rxt);
+    }
+
+    #[tokio::test]
+    async fn get_stage_rxt_returns_404_when_the_stage_is_missing() {
+        let id = ObjectId::new();
+        let mut mock_repo = MockDbRepository::new();
+        mock_repo
+            .expect_find_stage_by_id()
+            .with(mockall::predicate::eq(id))
+            .times(1)
+            .returning(|_| Ok(None));
+
+        let response = router(mock_repo)
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/stages/{}/rxt", id.to_hex()))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn list_stages_returns_the_repository_results() {
+        let stage = dummy_stage(ObjectId::new());
+        let expected = vec![stage.clone()];
+        let mut mock_repo = MockDbRepository::new();
+        mock_repo
+            .expect_find_stages_by_uri()
+            .with(mockall::predicate::eq("show/shot"), mockall::predicate::eq(None))
+            .times(1)
+            .returning(move |_, _| Ok(vec![stage.clone()]));
+
+        let response = router(mock_repo)
+            .oneshot(
+                Request::builder()
+                    .uri("/stages?uri=show%2Fshot")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_bytes(response).await;
+        let stages: Vec<Stage> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(stages, expected);
+    }
+}
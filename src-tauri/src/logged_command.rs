@@ -0,0 +1,254 @@
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::thread;
+
+use chrono::Utc;
+
+use crate::error::Error;
+
+/// Result of a `LoggedCommand::run()` invocation. `log_path` is always
+/// populated (success or failure) so the caller can surface "open full
+/// log" regardless of outcome.
+#[derive(Debug)]
+pub struct LoggedCommandResult {
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    pub log_path: PathBuf,
+}
+
+fn log_dir() -> PathBuf {
+    std::env::temp_dir().join("rezlauncher_logs")
+}
+
+/// Renders an `ExitStatus` identically across platforms: some OSes format
+/// it as "exit code: 0", others as "exit status: 0". We extract the
+/// numeric code ourselves so log files and error messages are consistent.
+fn format_exit_status(code: Option<i32>) -> String {
+    match code {
+        Some(code) => format!("exit code: {}", code),
+        None => "terminated by signal".to_string(),
+    }
+}
+
+/// Reads a child's stdout/stderr pipe to completion and splits it into
+/// lines. Reads raw bytes rather than `BufRead::lines()`: a single
+/// non-UTF-8 byte (locale-dependent `rez` messages, a progress spinner)
+/// would otherwise make `Lines` yield the same `Err` forever without
+/// consuming it, spinning this thread and hanging `run()`'s `join()`
+/// forever. `from_utf8_lossy` can't loop since `read_to_end` always makes
+/// progress until EOF.
+fn drain_to_lines<R: Read + Send + 'static>(mut reader: R) -> Vec<String> {
+    let mut buf = Vec::new();
+    let _ = reader.read_to_end(&mut buf);
+    String::from_utf8_lossy(&buf)
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+/// Wraps `std::process::Command` for every place the app shells out to
+/// `rez`: it pipes stdout/stderr, drains both on their own threads (so a
+/// resolve that fills the OS pipe buffer never deadlocks), and tees
+/// everything into a timestamped file under `rezlauncher_logs/` so a
+/// failed resolve can always be retrieved after the fact.
+pub struct LoggedCommand {
+    command: Command,
+    display_command: String,
+    label: String,
+}
+
+impl LoggedCommand {
+    pub fn new(program: &str, label: &str) -> Self {
+        LoggedCommand {
+            command: Command::new(program),
+            display_command: program.to_string(),
+            label: label.to_string(),
+        }
+    }
+
+    pub fn arg(mut self, arg: &str) -> Self {
+        self.display_command.push(' ');
+        self.display_command.push_str(arg);
+        self.command.arg(arg);
+        self
+    }
+
+    /// Builds the platform shell invocation (`cmd /c` / `sh -c`) for a full
+    /// command line, while keeping the logical command line (not the shell
+    /// wrapper) as what gets logged and reported in errors.
+    pub fn shell(command_line: &str, label: &str) -> Self {
+        let mut logged = if cfg!(target_os = "windows") {
+            LoggedCommand::new("cmd", label).arg("/c").arg(command_line)
+        } else {
+            LoggedCommand::new("sh", label).arg("-c").arg(command_line)
+        };
+        logged.display_command = command_line.to_string();
+        logged
+    }
+
+    fn open_log_file(&self) -> Result<(PathBuf, fs::File), Error> {
+        let dir = log_dir();
+        fs::create_dir_all(&dir)?;
+        let timestamp = Utc::now().format("%Y%m%d_%H%M%S%.3f");
+        let log_path = dir.join(format!("{}_{}.log", self.label, timestamp));
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&log_path)?;
+        Ok((log_path, file))
+    }
+
+    /// Runs the command to completion, capturing stdout/stderr and tee-ing
+    /// them into the log file. Returns `Error::RezCommand` (with
+    /// `log_path` populated) on a non-zero exit.
+    pub fn run(mut self) -> Result<LoggedCommandResult, Error> {
+        let (log_path, mut log_file) = self.open_log_file()?;
+        writeln!(log_file, "$ {}", self.display_command)?;
+
+        let mut child = self
+            .command
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| Error::RezCommand {
+                command: self.display_command.clone(),
+                stderr: e.to_string(),
+                log_path: Some(log_path.clone()),
+            })?;
+
+        let stdout = child.stdout.take().expect("stdout was piped");
+        let stderr = child.stderr.take().expect("stderr was piped");
+
+        let stdout_handle = thread::spawn(move || drain_to_lines(stdout));
+        let stderr_handle = thread::spawn(move || drain_to_lines(stderr));
+
+        let stdout_lines = stdout_handle.join().unwrap_or_default();
+        let stderr_lines = stderr_handle.join().unwrap_or_default();
+
+        for line in &stdout_lines {
+            writeln!(log_file, "{}", line)?;
+        }
+        for line in &stderr_lines {
+            writeln!(log_file, "[stderr] {}", line)?;
+        }
+
+        let status = child.wait().map_err(|e| Error::RezCommand {
+            command: self.display_command.clone(),
+            stderr: e.to_string(),
+            log_path: Some(log_path.clone()),
+        })?;
+        let exit_code = status.code();
+        writeln!(log_file, "-- {}", format_exit_status(exit_code))?;
+
+        let stdout_text = stdout_lines.join("\n");
+        let stderr_text = stderr_lines.join("\n");
+
+        if !status.success() {
+            return Err(Error::RezCommand {
+                command: self.display_command,
+                stderr: if stderr_text.is_empty() {
+                    format_exit_status(exit_code)
+                } else {
+                    stderr_text
+                },
+                log_path: Some(log_path),
+            });
+        }
+
+        Ok(LoggedCommandResult {
+            exit_code,
+            stdout: stdout_text,
+            stderr: stderr_text,
+            log_path,
+        })
+    }
+
+    /// Spawns the command without waiting for it to exit, for the
+    /// terminal-launching commands where the child is a detached terminal
+    /// emulator the user interacts with directly. Only the spawn attempt
+    /// itself is logged; returns the log path so a spawn failure can still
+    /// point at a file with the attempted command line.
+    pub fn spawn_detached(self) -> Result<PathBuf, Error> {
+        let (log_path, mut log_file) = self.open_log_file()?;
+        writeln!(log_file, "$ {}", self.display_command)?;
+
+        let mut command = self.command;
+        match command.spawn() {
+            Ok(_) => {
+                writeln!(log_file, "-- spawned detached")?;
+                Ok(log_path)
+            }
+            Err(e) => {
+                writeln!(log_file, "-- failed to spawn: {}", e)?;
+                Err(Error::RezCommand {
+                    command: self.display_command,
+                    stderr: e.to_string(),
+                    log_path: Some(log_path),
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_exit_status_renders_the_numeric_code() {
+        assert_eq!(format_exit_status(Some(0)), "exit code: 0");
+        assert_eq!(format_exit_status(Some(3)), "exit code: 3");
+    }
+
+    #[test]
+    fn format_exit_status_reports_signal_termination() {
+        assert_eq!(format_exit_status(None), "terminated by signal");
+    }
+
+    #[test]
+    fn run_captures_stdout_and_exit_code_on_success() {
+        let result = LoggedCommand::shell("echo out", "test_run_success")
+            .run()
+            .expect("sh -c echo should succeed");
+        assert_eq!(result.exit_code, Some(0));
+        assert_eq!(result.stdout, "out");
+        assert!(result.log_path.exists());
+        let log_contents = fs::read_to_string(&result.log_path).unwrap();
+        assert!(log_contents.contains("out"));
+    }
+
+    #[test]
+    fn run_returns_rez_command_error_with_log_path_on_non_zero_exit() {
+        let err = LoggedCommand::shell("echo out; echo err >&2; exit 3", "test_run_failure")
+            .run()
+            .expect_err("non-zero exit should error");
+        match err {
+            Error::RezCommand {
+                stderr, log_path, ..
+            } => {
+                assert_eq!(stderr, "err");
+                let log_path = log_path.expect("log path should be populated on failure");
+                assert!(log_path.exists());
+                let log_contents = fs::read_to_string(&log_path).unwrap();
+                assert!(log_contents.contains("err"));
+                assert!(log_contents.contains("exit code: 3"));
+            }
+            other => panic!("expected Error::RezCommand, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn spawn_detached_logs_the_attempted_command_and_returns_its_path() {
+        let log_path = LoggedCommand::shell("exit 0", "test_spawn_detached")
+            .spawn_detached()
+            .expect("spawn should succeed");
+        assert!(log_path.exists());
+        let log_contents = fs::read_to_string(&log_path).unwrap();
+        assert!(log_contents.contains("exit 0"));
+        assert!(log_contents.contains("spawned detached"));
+    }
+}
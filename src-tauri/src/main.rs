@@ -1,248 +1,57 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use async_trait::async_trait;
-use mongodb::{Client, options::ClientOptions, Collection, Database};
-use mongodb::bson::{doc, oid::ObjectId, Bson};
-use serde::{Deserialize, Serialize, de::DeserializeOwned};
+mod error;
+mod http_gateway;
+mod logged_command;
+mod repository;
+mod rez_toolchain;
+mod stage_diff;
+
+use mongodb::bson::oid::ObjectId;
 use chrono::Utc;
+use std::net::SocketAddr;
 use std::sync::{Arc, Mutex};
 use std::fs::{self, OpenOptions, File};
-use std::io::{Read, Write};
-use std::process::Command;
+use std::io::Write;
 use rand::Rng;
 use tauri::State;
-use futures::stream::StreamExt;
 use once_cell::sync::Lazy;
 
+use error::Error;
+use logged_command::LoggedCommand;
+use repository::{build_repository, DbRepository, PackageCollection, PackageCollectionResult, Stage};
+use rez_toolchain::{RezToolchain, min_rez_version};
+use stage_diff::{diff_stages, StageDiffReport};
+
 // Configuration par défaut de MongoDB (utilisée si aucune configuration n'est fournie)
 const DEFAULT_MONGO_URI: &str = "mongodb://localhost:27017";
-const DB_NAME: &str = "rez_launcher";
+
+// Active l'embedded HTTP gateway (lecture seule) si définie, par ex. "127.0.0.1:8420".
+// Désactivée par défaut.
+const HTTP_GATEWAY_BIND_ENV_VAR: &str = "REZLAUNCHER_HTTP_GATEWAY_BIND";
 
 // Variable globale pour stocker l'URI MongoDB actuelle
 static MONGO_URI: Lazy<Mutex<String>> = Lazy::new(|| Mutex::new(DEFAULT_MONGO_URI.to_string()));
 
-#[cfg_attr(test, mockall::automock)]
-#[async_trait]
-trait DbRepository: Send + Sync {
-    async fn find_package_collections_by_uri(&self, uri: &str) -> Result<Vec<PackageCollection>, String>;
-    async fn find_all_package_collections(&self) -> Result<Vec<PackageCollection>, String>;
-    async fn insert_package_collection(&self, package_data: PackageCollection) -> Result<(), String>;
-    async fn find_package_collection_tools(&self, version: &str, uri: &str) -> Result<Option<Vec<String>>, String>;
-    async fn find_stages_by_uri(&self, uri: &str, active_only: Option<bool>) -> Result<Vec<Stage>, String>;
-    async fn insert_stage(&self, stage_data: Stage) -> Result<(), String>;
-    async fn update_stages_active_status(&self, name: &str, uri: &str, active: bool) -> Result<(), String>;
-    async fn update_stage_active_status_by_id(&self, id: ObjectId, active: bool) -> Result<(), String>;
-    async fn find_stage_by_id(&self, id: ObjectId) -> Result<Option<Stage>, String>;
-    async fn find_stage_history(&self, stage_name: &str, uri: &str) -> Result<Vec<Stage>, String>;
-    async fn find_distinct_stage_names(&self) -> Result<Vec<String>, String>;
-}
-
-struct MongoDbRepository {
-    db: Database,
-    log_state: LogState,
-}
+pub(crate) struct LogState(pub(crate) Mutex<File>);
 
-impl MongoDbRepository {
-    fn get_collection<T>(&self, name: &str) -> Collection<T> {
-        self.db.collection::<T>(name)
-    }
-
-    async fn fetch_documents_internal<T>(
-        &self,
-        collection_name: &str,
-        filter: impl Into<Option<mongodb::bson::Document>>,
-        log_msg_prefix: &str,
-    ) -> Result<Vec<T>, String>
-    where
-        T: DeserializeOwned + Send + Sync + Unpin + Clone + std::fmt::Debug,
-    {
-        let collection = self.get_collection::<T>(collection_name);
-        let mut cursor = collection
-            .find(filter, None)
-            .await
-            .map_err(|e| e.to_string())?;
-
-        let mut documents = Vec::new();
-        while let Some(result) = cursor.next().await {
-            match result {
-                Ok(document) => documents.push(document),
-                Err(e) => log_message(&self.log_state, format!("Error fetching document: {}", e)),
-            }
-        }
-
-        #[cfg(debug_assertions)]
-        {
-            let doc_count = documents.len();
-            log_message(&self.log_state, format!("{}: {} documents retrieved.", log_msg_prefix, doc_count));
-        }
-        #[cfg(not(debug_assertions))]
-        log_message(&self.log_state, format!("{}: {}", log_msg_prefix, documents.len()));
-
-        Ok(documents)
+impl LogState {
+    pub(crate) fn try_clone(&self) -> Result<LogState, Error> {
+        let file = self.0.lock().unwrap().try_clone()?;
+        Ok(LogState(Mutex::new(file)))
     }
 }
 
-
-#[async_trait]
-impl DbRepository for MongoDbRepository {
-    async fn find_package_collections_by_uri(&self, uri: &str) -> Result<Vec<PackageCollection>, String> {
-        let filter = doc! { "uri": uri };
-        self.fetch_documents_internal(
-            "package_collections",
-            filter,
-            &format!("Retrieved package collections with URI: {}", uri)
-        ).await
-    }
-
-    async fn find_all_package_collections(&self) -> Result<Vec<PackageCollection>, String> {
-        self.fetch_documents_internal(
-            "package_collections",
-            None,
-            "Retrieved all package collections"
-        ).await
-    }
-
-     async fn insert_package_collection(&self, package_data: PackageCollection) -> Result<(), String> {
-        let collection = self.get_collection::<PackageCollection>("package_collections");
-        collection
-            .insert_one(package_data, None)
-            .await
-            .map_err(|e| e.to_string())?;
-        Ok(())
-    }
-
-    async fn find_package_collection_tools(&self, version: &str, uri: &str) -> Result<Option<Vec<String>>, String> {
-        let collection = self.get_collection::<PackageCollection>("package_collections");
-        let filter = doc! { "version": version, "uri": uri };
-        match collection.find_one(filter, None).await {
-            Ok(Some(package)) => Ok(Some(package.tools)),
-            Ok(None) => Ok(None),
-            Err(e) => Err(e.to_string()),
-        }
-    }
-
-    async fn find_stages_by_uri(&self, uri: &str, active_only: Option<bool>) -> Result<Vec<Stage>, String> {
-        let mut filter = doc! { "uri": uri };
-        if let Some(true) = active_only {
-            filter.insert("active", true);
-        }
-        let filter_status = if active_only.unwrap_or(false) { "active " } else { "" };
-        let log_msg = format!("Retrieved {}stages with URI: {}", filter_status, uri);
-        self.fetch_documents_internal("stages", filter, &log_msg).await
-    }
-
-     async fn insert_stage(&self, stage_data: Stage) -> Result<(), String> {
-        let collection = self.get_collection::<Stage>("stages");
-        collection
-            .insert_one(stage_data, None)
-            .await
-            .map_err(|e| e.to_string())?;
-        Ok(())
-    }
-
-    async fn update_stages_active_status(&self, name: &str, uri: &str, active: bool) -> Result<(), String> {
-        let collection = self.get_collection::<Stage>("stages");
-        let filter = doc! { "name": name, "uri": uri };
-        let update = doc! { "$set": { "active": active } };
-        collection
-            .update_many(filter, update, None)
-            .await
-            .map_err(|e| e.to_string())?;
-        Ok(())
-    }
-
-     async fn update_stage_active_status_by_id(&self, id: ObjectId, active: bool) -> Result<(), String> {
-        let collection = self.get_collection::<Stage>("stages");
-        let filter = doc! { "_id": id };
-        let update = doc! { "$set": { "active": active } };
-        collection
-            .update_one(filter, update, None)
-            .await
-            .map_err(|e| e.to_string())?;
-        Ok(())
-    }
-
-    async fn find_stage_by_id(&self, id: ObjectId) -> Result<Option<Stage>, String> {
-        let collection = self.get_collection::<Stage>("stages");
-        let filter = doc! { "_id": id };
-        collection
-            .find_one(filter, None)
-            .await
-            .map_err(|e| e.to_string())
-    }
-
-    async fn find_stage_history(&self, stage_name: &str, uri: &str) -> Result<Vec<Stage>, String> {
-        let filter = doc! { "name": stage_name, "uri": uri };
-        let log_msg = format!("Retrieved stage versions for '{}' with URI '{}'", stage_name, uri);
-        self.fetch_documents_internal("stages", filter, &log_msg).await
-    }
-
-    async fn find_distinct_stage_names(&self) -> Result<Vec<String>, String> {
-        let collection = self.get_collection::<Stage>("stages");
-        log_message(&self.log_state, "Fetching all unique stage names".to_string());
-        match collection.distinct("name", None, None).await {
-            Ok(names_bson) => {
-                let names: Vec<String> = names_bson.into_iter()
-                    .filter_map(|bson| match bson {
-                        Bson::String(s) => Some(s),
-                        _ => {
-                            log_message(&self.log_state, format!("Non-string value found in distinct stage names: {:?}", bson));
-                            None
-                        }
-                    })
-                    .collect();
-                log_message(&self.log_state, format!("Retrieved {} unique stage names", names.len()));
-                Ok(names)
-            }
-            Err(e) => {
-                let error_msg = format!("Error fetching distinct stage names: {}", e);
-                log_message(&self.log_state, error_msg.clone());
-                Err(error_msg)
-            }
-        }
-    }
-}
-
-struct LogState(Mutex<File>);
-
 struct AppState {
     db_repo: Arc<dyn DbRepository>,
     log_state: LogState,
+    // Cached so each launch doesn't re-probe `rez --version`; `None` means
+    // detection failed (e.g. rez not on PATH) and commands that need it
+    // will surface that as an error rather than a raw spawn failure.
+    rez_toolchain: Mutex<Option<RezToolchain>>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
-struct PackageCollection {
-    version: String,
-    packages: Vec<String>,
-    herit: String,
-    tools: Vec<String>,
-    created_at: String,
-    created_by: String,
-    uri: String,
-}
-
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
-struct Stage {
-    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
-    id: Option<ObjectId>,
-    name: String,
-    uri: String,
-    from_version: String,
-    rxt: String,
-    tools: Vec<String>,
-    created_at: String,
-    created_by: String,
-    active: bool,
-}
-
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
-struct PackageCollectionResult {
-    success: bool,
-    message: Option<String>,
-    collections: Option<Vec<PackageCollection>>,
-}
-
-fn log_message(log_state: &LogState, message: String) {
+pub(crate) fn log_message(log_state: &LogState, message: String) {
     let mut log_file = match log_state.0.lock() {
         Ok(file) => file,
         Err(e) => {
@@ -262,35 +71,61 @@ fn log_message(log_state: &LogState, message: String) {
     println!("{}", log_entry.trim());
 }
 
-fn init_log_file() -> Result<File, String> {
+fn init_log_file() -> Result<File, Error> {
     let temp_dir = std::env::temp_dir();
     let log_dir = temp_dir.join("rezlauncher_logs");
 
-    if (!log_dir.exists()) {
-        std::fs::create_dir_all(&log_dir).map_err(|e| e.to_string())?;
+    if !log_dir.exists() {
+        std::fs::create_dir_all(&log_dir)?;
     }
 
     let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
     let log_path = log_dir.join(format!("rezlauncher_{}.log", timestamp));
 
-    OpenOptions::new()
+    Ok(OpenOptions::new()
         .create(true)
         .write(true)
         .append(true)
-        .open(&log_path)
-        .map_err(|e| format!("Failed to open log file: {}", e))
+        .open(&log_path)?)
 }
 
 #[tauri::command]
-async fn init_command() -> Result<bool, String> {
+async fn init_command() -> Result<bool, Error> {
     Ok(true)
 }
 
+/// Re-probes the `rez` installation and refreshes the cache, so the UI can
+/// call this on startup to warn the user early instead of letting them
+/// discover a missing/too-old rez through a cryptic resolve failure.
+#[tauri::command]
+async fn check_rez_installation(state: State<'_, AppState>) -> Result<RezToolchain, Error> {
+    let toolchain = RezToolchain::detect(min_rez_version())?;
+    log_message(
+        &state.log_state,
+        format!(
+            "rez toolchain detected: {}.{}.{} at {}",
+            toolchain.version.0, toolchain.version.1, toolchain.version.2,
+            toolchain.executable_path.display()
+        ),
+    );
+    let result = toolchain.clone();
+    *state.rez_toolchain.lock().unwrap() = Some(toolchain);
+    Ok(result)
+}
+
+/// Returns the cached `rez` toolchain, or a structured error guiding the
+/// user to fix their installation if detection never succeeded.
+fn require_rez_toolchain(state: &AppState) -> Result<RezToolchain, Error> {
+    state.rez_toolchain.lock().unwrap().clone().ok_or_else(|| Error::Other(
+        "rez was not detected on this machine. Run the rez installation check and ensure rez is on PATH.".to_string()
+    ))
+}
+
 #[tauri::command]
 async fn save_package_collection(
     package_data: PackageCollection,
     state: State<'_, AppState>,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     state.db_repo.insert_package_collection(package_data.clone()).await?;
     log_message(
         &state.log_state,
@@ -303,7 +138,7 @@ async fn save_package_collection(
 async fn save_stage_to_mongodb(
     stage_data: Stage,
     state: State<'_, AppState>,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     // First, find the source package collection to get the list of packages
     let source_package = state.db_repo.find_package_collections_by_uri(&stage_data.uri)
         .await?
@@ -313,9 +148,12 @@ async fn save_stage_to_mongodb(
     let packages = match source_package {
         Some(pkg) => pkg.packages,
         None => {
-            let error_msg = format!("Package collection {} not found for RXT generation", stage_data.from_version);
-            log_message(&state.log_state, error_msg.clone());
-            return Err(error_msg);
+            let err = Error::PackageCollectionNotFound {
+                version: stage_data.from_version.clone(),
+                uri: stage_data.uri.clone(),
+            };
+            log_message(&state.log_state, err.to_string());
+            return Err(err);
         }
     };
 
@@ -325,7 +163,8 @@ async fn save_stage_to_mongodb(
         format!("Generating RXT file for stage '{}' with {} packages", stage_data.name, packages.len())
     );
 
-    let rxt_content = match generate_rxt_file(&packages, &state.log_state).await {
+    let toolchain = require_rez_toolchain(&state)?;
+    let rxt_content = match generate_rxt_file(&packages, &toolchain, &state.log_state).await {
         Ok(content) => {
             log_message(
                 &state.log_state,
@@ -334,9 +173,8 @@ async fn save_stage_to_mongodb(
             content
         },
         Err(e) => {
-            let error_msg = format!("Failed to generate RXT file: {}", e);
-            log_message(&state.log_state, error_msg.clone());
-            return Err(error_msg);
+            log_message(&state.log_state, format!("Failed to generate RXT file: {}", e));
+            return Err(e);
         }
     };
 
@@ -367,7 +205,7 @@ async fn save_stage_to_mongodb(
 async fn get_package_collections_by_uri(
     uri: String,
     state: State<'_, AppState>,
-) -> Result<PackageCollectionResult, String> {
+) -> Result<PackageCollectionResult, Error> {
     let packages = state.db_repo.find_package_collections_by_uri(&uri).await?;
 
     if packages.is_empty() {
@@ -388,7 +226,7 @@ async fn get_package_collections_by_uri(
 #[tauri::command]
 async fn get_all_package_collections(
     state: State<'_, AppState>,
-) -> Result<PackageCollectionResult, String> {
+) -> Result<PackageCollectionResult, Error> {
     let packages = state.db_repo.find_all_package_collections().await?;
 
     if packages.is_empty() {
@@ -411,7 +249,7 @@ async fn get_package_collection_tools(
     version: String,
     uri: String,
     state: State<'_, AppState>,
-) -> Result<Vec<String>, String> {
+) -> Result<Vec<String>, Error> {
     match state.db_repo.find_package_collection_tools(&version, &uri).await? {
         Some(tools) => {
             log_message(&state.log_state, format!("Found package collection with {} tools via repository", tools.len()));
@@ -429,7 +267,7 @@ async fn get_stages_by_uri(
     uri: String,
     active_only: Option<bool>,
     state: State<'_, AppState>,
-) -> Result<Vec<Stage>, String> {
+) -> Result<Vec<Stage>, Error> {
     state.db_repo.find_stages_by_uri(&uri, active_only).await
 }
 
@@ -437,11 +275,11 @@ async fn get_stages_by_uri(
 async fn revert_stage(
     stage_id: String,
     state: State<'_, AppState>,
-) -> Result<bool, String> {
-    let object_id = ObjectId::parse_str(&stage_id).map_err(|e| e.to_string())?;
+) -> Result<bool, Error> {
+    let object_id = ObjectId::parse_str(&stage_id).map_err(|_| Error::InvalidObjectId(stage_id.clone()))?;
 
     let stage_to_activate = state.db_repo.find_stage_by_id(object_id).await?
-        .ok_or_else(|| "Stage not found".to_string())?;
+        .ok_or(Error::StageNotFound(object_id))?;
 
     let stage_name = stage_to_activate.name.clone();
     let stage_uri = stage_to_activate.uri.clone();
@@ -473,69 +311,102 @@ async fn get_stage_history(
     stage_name: String,
     uri: String,
     state: State<'_, AppState>,
-) -> Result<Vec<Stage>, String> {
+) -> Result<Vec<Stage>, Error> {
     state.db_repo.find_stage_history(&stage_name, &uri).await
 }
 
+/// Audit-trail report for a `revert_stage` decision: loads the two saved
+/// versions of `stage_name` and diffs their tools and resolved packages, so
+/// the UI can show what would actually change instead of raw RXT text.
 #[tauri::command]
-fn get_current_username() -> Result<String, String> {
+async fn get_stage_diff(
+    stage_name: String,
+    uri: String,
+    from_id: String,
+    to_id: String,
+    state: State<'_, AppState>,
+) -> Result<StageDiffReport, Error> {
+    let from_object_id = ObjectId::parse_str(&from_id).map_err(|_| Error::InvalidObjectId(from_id.clone()))?;
+    let to_object_id = ObjectId::parse_str(&to_id).map_err(|_| Error::InvalidObjectId(to_id.clone()))?;
+
+    let from_stage = state.db_repo.find_stage_by_id(from_object_id).await?
+        .ok_or(Error::StageNotFound(from_object_id))?;
+    let to_stage = state.db_repo.find_stage_by_id(to_object_id).await?
+        .ok_or(Error::StageNotFound(to_object_id))?;
+
+    if from_stage.name != stage_name || from_stage.uri != uri {
+        return Err(Error::StageNotFound(from_object_id));
+    }
+    if to_stage.name != stage_name || to_stage.uri != uri {
+        return Err(Error::StageNotFound(to_object_id));
+    }
+
+    log_message(
+        &state.log_state,
+        format!("Computing stage diff for '{}' ({}) between {} and {}", stage_name, uri, from_id, to_id)
+    );
+
+    Ok(diff_stages(&from_stage, &to_stage))
+}
+
+#[tauri::command]
+fn get_current_username() -> Result<String, Error> {
     std::env::var("USERNAME")
         .or_else(|_| std::env::var("USER"))
-        .map_err(|e| format!("Failed to get username: {}", e))
+        .map_err(|e| Error::Other(format!("Failed to get username: {}", e)))
 }
 
 #[tauri::command]
 async fn get_all_stage_names(
     state: State<'_, AppState>,
-) -> Result<Vec<String>, String> {
+) -> Result<Vec<String>, Error> {
     state.db_repo.find_distinct_stage_names().await
 }
 
 #[tauri::command]
-async fn open_tool_in_terminal(tool_name: String, packages: Vec<String>, state: State<'_, AppState>) -> Result<bool, String> {
+async fn open_tool_in_terminal(tool_name: String, packages: Vec<String>, state: State<'_, AppState>) -> Result<bool, Error> {
     log_message(&state.log_state, format!("Attempting to open tool: {} with packages: {:?}", tool_name, packages));
 
-    // Construire la commande rez env avec la liste des packages
-    let packages_str = packages.join(" ");
-    let rez_command = format!("rez env {} -- {}", packages_str, tool_name);
-    log_message(&state.log_state, format!("Executing rez command: {}", rez_command));
+    let toolchain = require_rez_toolchain(&state)?;
 
-    let mut command = if cfg!(target_os = "windows") {
-        let mut cmd = std::process::Command::new("cmd");
-        cmd.arg("/c").arg(&rez_command);
-        cmd
-    } else {
-        let mut cmd = std::process::Command::new("sh");
-        cmd.arg("-c").arg(&rez_command);
-        cmd
-    };
+    // Invoke the resolved rez executable directly, one argument per package
+    let mut command = toolchain.command("open_tool_in_terminal").arg("env");
+    for package in &packages {
+        command = command.arg(package);
+    }
+    command = command.arg("--").arg(&tool_name);
+    log_message(&state.log_state, format!("Executing: {} env {:?} -- {}", toolchain.executable_path.display(), packages, tool_name));
 
-    match command.spawn() {
-        Ok(_) => {
-            log_message(&state.log_state, format!("Tool launched successfully in rez environment: {}", tool_name));
+    match command.spawn_detached() {
+        Ok(log_path) => {
+            log_message(&state.log_state, format!("Tool launched successfully in rez environment: {} (log: {})", tool_name, log_path.display()));
             Ok(true)
         },
         Err(e) => {
             log_message(&state.log_state, format!("Failed to launch tool in rez environment: {}", e));
-            Err(format!("Failed to launch tool in rez environment: {}", e))
+            Err(e)
         }
     }
 }
 
 #[tauri::command]
-async fn open_rez_env_in_terminal(packages: Vec<String>, state: State<'_, AppState>) -> Result<bool, String> {
+async fn open_rez_env_in_terminal(packages: Vec<String>, state: State<'_, AppState>) -> Result<bool, Error> {
     log_message(&state.log_state, format!("Attempting to open rez environment with packages: {:?}", packages));
 
-    // Construire la commande rez env avec la liste des packages
-    let packages_str = packages.join(" ");
-    let rez_command = format!("rez env {}", packages_str);
-    log_message(&state.log_state, format!("Executing rez command in new terminal: {}", rez_command));
+    let toolchain = require_rez_toolchain(&state)?;
+
+    // Invoke the resolved rez executable directly, one argument per package
+    // (no shell string interpolation, so a package name can't inject extra
+    // commands into the spawned terminal). `rez env` with no trailing
+    // command drops the user into its own interactive subshell, so the
+    // window stays open without needing a "&& bash" wrapper.
+    let rez_path = toolchain.executable_path.to_string_lossy().to_string();
+    log_message(&state.log_state, format!("Executing in new terminal: {} env {:?}", rez_path, packages));
 
     let mut command = if cfg!(target_os = "windows") {
         // Sur Windows, utiliser "start cmd" pour ouvrir une nouvelle fenêtre de terminal
-        let mut cmd = std::process::Command::new("cmd");
-        cmd.arg("/c").arg("start").arg("cmd").arg("/k").arg(&rez_command);
-        cmd
+        LoggedCommand::new("cmd", "open_rez_env_in_terminal")
+            .arg("/c").arg("start").arg("cmd").arg("/k").arg(&rez_path).arg("env")
     } else {
         // Sur Linux/Mac, utiliser xterm ou terminal
         let terminal_cmd = if std::path::Path::new("/usr/bin/xterm").exists() {
@@ -546,57 +417,41 @@ async fn open_rez_env_in_terminal(packages: Vec<String>, state: State<'_, AppSta
             "x-terminal-emulator"
         };
 
-        let mut cmd = std::process::Command::new(terminal_cmd);
-        cmd.arg("-e").arg(format!("bash -c '{} && bash'", rez_command));
-        cmd
+        LoggedCommand::new(terminal_cmd, "open_rez_env_in_terminal")
+            .arg("-e").arg(&rez_path).arg("env")
     };
+    for package in &packages {
+        command = command.arg(package);
+    }
 
-    match command.spawn() {
-        Ok(_) => {
-            log_message(&state.log_state, format!("Rez environment opened successfully in new terminal with packages: {}", packages_str));
+    match command.spawn_detached() {
+        Ok(log_path) => {
+            log_message(&state.log_state, format!("Rez environment opened successfully in new terminal with packages: {:?} (log: {})", packages, log_path.display()));
             Ok(true)
         },
         Err(e) => {
             log_message(&state.log_state, format!("Failed to open rez environment in new terminal: {}", e));
-            Err(format!("Failed to open rez environment in new terminal: {}", e))
+            Err(e)
         }
     }
 }
 
 #[tauri::command]
-async fn test_mongodb_connection(mongo_uri: String) -> Result<bool, String> {
-    // Mettre à jour l'URI globale si la connexion réussit
-    match ClientOptions::parse(&mongo_uri).await {
-        Ok(options) => {
-            match Client::with_options(options) {
-                Ok(client) => {
-                    // Tester la connexion avec un ping
-                    match client.database("admin").run_command(doc! {"ping": 1}, None).await {
-                        Ok(_) => {
-                            // Connexion réussie, mettre à jour l'URI globale
-                            let mut current_uri = MONGO_URI.lock().unwrap();
-                            *current_uri = mongo_uri;
-                            Ok(true)
-                        },
-                        Err(e) => {
-                            Err(format!("Échec du ping MongoDB: {}", e))
-                        }
-                    }
-                },
-                Err(e) => {
-                    Err(format!("Impossible de créer le client MongoDB: {}", e))
-                }
-            }
-        },
-        Err(e) => {
-            Err(format!("URI MongoDB invalide: {}", e))
-        }
-    }
+async fn test_mongodb_connection(mongo_uri: String, state: State<'_, AppState>) -> Result<bool, Error> {
+    // Construit le backend correspondant au schéma de l'URI et vérifie la connectivité
+    let probe_log_state = state.log_state.try_clone()?;
+    let repo = build_repository(&mongo_uri, probe_log_state).await?;
+    repo.ping().await?;
+
+    // Connexion réussie, mettre à jour l'URI globale
+    let mut current_uri = MONGO_URI.lock().unwrap();
+    *current_uri = mongo_uri;
+    Ok(true)
 }
 
 // Generate an RXT file from a list of packages using the rez env command
 // Returns the content of the RXT file as a string
-async fn generate_rxt_file(packages: &[String], log_state: &LogState) -> Result<String, String> {
+async fn generate_rxt_file(packages: &[String], toolchain: &RezToolchain, log_state: &LogState) -> Result<String, Error> {
     log_message(log_state, format!("Generating RXT file for packages: {:?}", packages));
 
     // Create a temporary file path
@@ -613,71 +468,49 @@ async fn generate_rxt_file(packages: &[String], log_state: &LogState) -> Result<
 
     log_message(log_state, format!("Using temporary file: {}", temp_file_path_str));
 
-    // Build the rez env command
-    let packages_str = packages.join(" ");
-    let rez_command = format!("rez env {} -o {}", packages_str, temp_file_path_str);
-    log_message(log_state, format!("Executing rez command: {}", rez_command));
-
-    // Execute the command
-    let output = if cfg!(target_os = "windows") {
-        Command::new("cmd")
-            .arg("/c")
-            .arg(&rez_command)
-            .output()
-    } else {
-        Command::new("sh")
-            .arg("-c")
-            .arg(&rez_command)
-            .output()
-    };
+    // Invoke the resolved rez executable directly (no PATH lookup in a
+    // spawned shell) with one argument per package
+    let mut command = toolchain.command("generate_rxt_file").arg("env");
+    for package in packages {
+        command = command.arg(package);
+    }
+    command = command.arg("-o").arg(&temp_file_path_str);
+    log_message(log_state, format!("Executing: {} env {:?} -o {}", toolchain.executable_path.display(), packages, temp_file_path_str));
 
-    // Check if command execution was successful
-    match output {
-        Ok(output) => {
-            if !output.status.success() {
-                let error = String::from_utf8_lossy(&output.stderr).to_string();
-                log_message(log_state, format!("Failed to generate RXT file: {}", error));
-                return Err(format!("Failed to generate RXT file: {}", error));
-            }
+    // Execute the command, capturing stdout/stderr into a retrievable log
+    let result = command.run()?;
+    log_message(log_state, format!("rez env resolve log captured at {}", result.log_path.display()));
 
-            // Read the content of the generated RXT file
-            match fs::read_to_string(&temp_file_path) {
-                Ok(content) => {
-                    log_message(log_state, format!("Successfully read RXT file (size: {} bytes)", content.len()));
+    // Read the content of the generated RXT file
+    let content = fs::read_to_string(&temp_file_path)
+        .map_err(|e| {
+            log_message(log_state, format!("Failed to read RXT file: {}", e));
+            Error::RxtGeneration(e.to_string())
+        })?;
 
-                    // Delete the temporary file
-                    if let Err(e) = fs::remove_file(&temp_file_path) {
-                        log_message(log_state, format!("Warning: Failed to delete temporary RXT file: {}", e));
-                    } else {
-                        log_message(log_state, format!("Deleted temporary RXT file: {}", temp_file_path_str));
-                    }
+    log_message(log_state, format!("Successfully read RXT file (size: {} bytes)", content.len()));
 
-                    Ok(content)
-                },
-                Err(e) => {
-                    log_message(log_state, format!("Failed to read RXT file: {}", e));
-                    Err(format!("Failed to read RXT file: {}", e))
-                }
-            }
-        },
-        Err(e) => {
-            log_message(log_state, format!("Failed to execute rez command: {}", e));
-            Err(format!("Failed to execute rez command: {}", e))
-        }
+    // Delete the temporary file
+    if let Err(e) = fs::remove_file(&temp_file_path) {
+        log_message(log_state, format!("Warning: Failed to delete temporary RXT file: {}", e));
+    } else {
+        log_message(log_state, format!("Deleted temporary RXT file: {}", temp_file_path_str));
     }
+
+    Ok(content)
 }
 
 #[tauri::command]
 async fn load_stage_by_id(
     stage_id: String,
     state: State<'_, AppState>,
-) -> Result<bool, String> {
+) -> Result<bool, Error> {
     // Parse the ObjectId
-    let object_id = ObjectId::parse_str(&stage_id).map_err(|e| e.to_string())?;
+    let object_id = ObjectId::parse_str(&stage_id).map_err(|_| Error::InvalidObjectId(stage_id.clone()))?;
 
     // Find the stage by ID
     let stage = state.db_repo.find_stage_by_id(object_id).await?
-        .ok_or_else(|| "Stage not found".to_string())?;
+        .ok_or(Error::StageNotFound(object_id))?;
 
     log_message(
         &state.log_state,
@@ -685,9 +518,11 @@ async fn load_stage_by_id(
     );
 
     if stage.rxt.is_empty() {
-        return Err("Stage has no RXT content".to_string());
+        return Err(Error::RxtGeneration("Stage has no RXT content".to_string()));
     }
 
+    let toolchain = require_rez_toolchain(&state)?;
+
     // Create a temporary file for the RXT content
     let temp_dir = std::env::temp_dir();
     let timestamp = Utc::now().format("%Y%m%d_%H%M%S").to_string();
@@ -703,18 +538,17 @@ async fn load_stage_by_id(
     log_message(&state.log_state, format!("Saving RXT content to temporary file: {}", temp_file_path_str));
 
     // Write the RXT content to the temporary file
-    fs::write(&temp_file_path, &stage.rxt)
-        .map_err(|e| format!("Failed to write RXT content to file: {}", e))?;
+    fs::write(&temp_file_path, &stage.rxt)?;
 
-    // Build the rez command to load the RXT environment
-    let rez_command = format!("rez env -i {}", temp_file_path_str);
-    log_message(&state.log_state, format!("Executing rez command: {}", rez_command));
+    // Invoke the resolved rez executable directly, one argument per flag
+    // (no shell string interpolation), in a new terminal
+    let rez_path = toolchain.executable_path.to_string_lossy().to_string();
+    log_message(&state.log_state, format!("Executing in new terminal: {} env -i {}", rez_path, temp_file_path_str));
 
-    // Execute the command in a new terminal
-    let mut command = if cfg!(target_os = "windows") {
-        let mut cmd = std::process::Command::new("cmd");
-        cmd.arg("/c").arg("start").arg("cmd").arg("/k").arg(&rez_command);
-        cmd
+    let command = if cfg!(target_os = "windows") {
+        LoggedCommand::new("cmd", "load_stage_by_id")
+            .arg("/c").arg("start").arg("cmd").arg("/k")
+            .arg(&rez_path).arg("env").arg("-i").arg(&temp_file_path_str)
     } else {
         // On Linux/Mac, use xterm or terminal
         let terminal_cmd = if std::path::Path::new("/usr/bin/xterm").exists() {
@@ -725,23 +559,21 @@ async fn load_stage_by_id(
             "x-terminal-emulator"
         };
 
-        let mut cmd = std::process::Command::new(terminal_cmd);
-        cmd.arg("-e").arg(format!("bash -c '{} && bash'", rez_command));
-        cmd
+        LoggedCommand::new(terminal_cmd, "load_stage_by_id")
+            .arg("-e").arg(&rez_path).arg("env").arg("-i").arg(&temp_file_path_str)
     };
 
-    match command.spawn() {
-        Ok(_) => {
+    match command.spawn_detached() {
+        Ok(log_path) => {
             log_message(
                 &state.log_state,
-                format!("Rez environment loaded successfully for stage '{}' using RXT file", stage.name)
+                format!("Rez environment loaded successfully for stage '{}' using RXT file (log: {})", stage.name, log_path.display())
             );
             Ok(true)
         },
         Err(e) => {
-            let error_msg = format!("Failed to launch rez environment: {}", e);
-            log_message(&state.log_state, error_msg.clone());
-            Err(error_msg)
+            log_message(&state.log_state, format!("Failed to launch rez environment: {}", e));
+            Err(e)
         }
     }
 }
@@ -757,63 +589,83 @@ fn main() {
     let log_state = LogState(Mutex::new(log_file));
 
     let app_state = tauri::async_runtime::block_on(async {
-        // Récupérer l'URI MongoDB actuelle depuis la variable globale
-        let mongo_uri = MONGO_URI.lock().unwrap().clone();
-        log_message(&log_state, format!("Initializing MongoDB connection with URI: {}", mongo_uri.split('@').next().unwrap_or(&mongo_uri)));
+        // Récupérer l'URI du repository actuelle depuis la variable globale
+        let repo_uri = MONGO_URI.lock().unwrap().clone();
+        log_message(&log_state, format!("Initializing repository with URI: {}", repo_uri.split('@').next().unwrap_or(&repo_uri)));
 
-        let client_options = match ClientOptions::parse(&mongo_uri).await {
-            Ok(options) => options,
-            Err(e) => {
-                log_message(&log_state, format!("Failed to parse MongoDB URI: {}", e));
-                // Continuer avec l'URI par défaut si l'URI configurée est invalide
-                let default_uri = DEFAULT_MONGO_URI.to_string();
-                log_message(&log_state, format!("Falling back to default URI: {}", default_uri));
+        let repo_log_state = log_state.try_clone().expect("Failed to clone log file handle during init");
 
-                ClientOptions::parse(DEFAULT_MONGO_URI)
-                    .await
-                    .expect("Failed to parse default MongoDB URI")
-            }
-        };
-
-        let client = match Client::with_options(client_options) {
-            Ok(client) => client,
+        // Construction du backend via la factory, en repli sur l'URI par défaut
+        // si l'URI configurée est invalide (schéma inconnu, URI malformée, ...)
+        let db_repo: Arc<dyn DbRepository> = match build_repository(&repo_uri, repo_log_state).await {
+            Ok(repo) => repo,
             Err(e) => {
-                log_message(&log_state, format!("Failed to create MongoDB client: {}", e));
-                // Au lieu de planter, on crée un client avec une URI par défaut
-                // qui sera remplacée plus tard par la configuration utilisateur
-                log_message(&log_state, "Creating placeholder MongoDB client - connection will be established later".to_string());
-                Client::with_uri_str(DEFAULT_MONGO_URI)
+                log_message(&log_state, format!("Failed to initialize repository with configured URI: {}", e));
+                log_message(&log_state, format!("Falling back to default URI: {}", DEFAULT_MONGO_URI));
+
+                let fallback_log_state = log_state.try_clone().expect("Failed to clone log file handle during init");
+                build_repository(DEFAULT_MONGO_URI, fallback_log_state)
                     .await
-                    .expect("Failed to create placeholder MongoDB client")
+                    .expect("Failed to initialize default repository backend")
             }
         };
 
-        // Essayer de ping MongoDB, mais ne pas planter si ça échoue
-        match client.database("admin").run_command(doc! {"ping": 1}, None).await {
-            Ok(_) => log_message(&log_state, "Connected to MongoDB successfully during init".to_string()),
+        // Essayer de ping le backend, mais ne pas planter si ça échoue : les
+        // fonctions individuelles géreront les erreurs de connexion quand
+        // elles seront appelées
+        match db_repo.ping().await {
+            Ok(_) => log_message(&log_state, "Connected to repository backend successfully during init".to_string()),
             Err(e) => {
-                log_message(&log_state, format!("Failed to ping MongoDB: {}", e));
-                log_message(&log_state, "Application will start and prompt for MongoDB configuration".to_string());
+                log_message(&log_state, format!("Failed to ping repository backend: {}", e));
+                log_message(&log_state, "Application will start and prompt for configuration".to_string());
                 // Ne pas panic! ici - on laisse l'interface s'afficher
             }
         }
 
-        let db = client.database(DB_NAME);
-        let cloned_log_file = log_state.0.lock().unwrap().try_clone().expect("Failed to clone log file handle during init");
-        let repo_log_state = LogState(Mutex::new(cloned_log_file));
-
-        // Création d'un repository MongoDB même si la connexion a échoué
-        // Les fonctions individuelles géreront les erreurs de connexion quand elles seront appelées
-        let db_repo: Arc<dyn DbRepository> = Arc::new(MongoDbRepository { db, log_state: repo_log_state });
+        // Probe rez once at startup and cache the result; a missing/too-old
+        // rez is reported by check_rez_installation and by the commands
+        // that need it, instead of failing app startup outright.
+        let rez_toolchain = match RezToolchain::detect(min_rez_version()) {
+            Ok(toolchain) => {
+                log_message(&log_state, format!(
+                    "rez toolchain detected: {}.{}.{} at {}",
+                    toolchain.version.0, toolchain.version.1, toolchain.version.2,
+                    toolchain.executable_path.display()
+                ));
+                Some(toolchain)
+            }
+            Err(e) => {
+                log_message(&log_state, format!("rez toolchain detection failed: {}", e));
+                None
+            }
+        };
 
-        AppState { db_repo, log_state }
+        AppState { db_repo, log_state, rez_toolchain: Mutex::new(rez_toolchain) }
     });
 
+    // Démarre l'embedded HTTP gateway (lecture seule) si une adresse de bind
+    // est configurée ; désactivée par défaut.
+    if let Ok(bind_str) = std::env::var(HTTP_GATEWAY_BIND_ENV_VAR) {
+        match bind_str.parse::<SocketAddr>() {
+            Ok(bind_addr) => {
+                let gateway_repo = Arc::clone(&app_state.db_repo);
+                let gateway_log_state = app_state.log_state.try_clone()
+                    .expect("Failed to clone log file handle for HTTP gateway");
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = http_gateway::serve(bind_addr, gateway_repo, gateway_log_state).await {
+                        eprintln!("HTTP gateway stopped unexpectedly: {}", e);
+                    }
+                });
+            }
+            Err(e) => eprintln!("Invalid {} value '{}': {}", HTTP_GATEWAY_BIND_ENV_VAR, bind_str, e),
+        }
+    }
 
     tauri::Builder::default()
         .manage(app_state)
         .invoke_handler(tauri::generate_handler![
             init_command,
+            check_rez_installation,
             save_package_collection,
             save_stage_to_mongodb,
             get_package_collections_by_uri,
@@ -823,6 +675,7 @@ fn main() {
             get_stages_by_uri,
             revert_stage,
             get_stage_history,
+            get_stage_diff,
             get_all_stage_names,
             open_tool_in_terminal,
             open_rez_env_in_terminal,
@@ -840,7 +693,7 @@ fn main() {
 mod tests {
     use super::*;
     use mockall::predicate::*;
-    use super::MockDbRepository;
+    use crate::repository::MockDbRepository;
     use rand::{distributions::Alphanumeric, Rng};
     use std::fs;
     use std::path::PathBuf;
@@ -897,6 +750,7 @@ mod tests {
         let app_state = AppState {
             db_repo: Arc::new(mock_repo),
             log_state,
+            rez_toolchain: Mutex::new(None),
         };
 
         let result = app_state.db_repo.find_package_collections_by_uri(uri1).await;
@@ -925,6 +779,7 @@ mod tests {
         let app_state = AppState {
             db_repo: Arc::new(mock_repo),
             log_state,
+            rez_toolchain: Mutex::new(None),
         };
 
         let result = app_state.db_repo.find_package_collections_by_uri(non_existent_uri).await;
@@ -939,24 +794,24 @@ mod tests {
      #[tokio::test]
     async fn test_get_package_collections_by_uri_repo_error() {
          let uri = "test/uri/error";
-         let error_message = "Database connection failed".to_string();
 
          let mut mock_repo = MockDbRepository::new();
          mock_repo.expect_find_package_collections_by_uri()
              .with(eq(uri))
              .times(1)
-             .returning(move |_| Err(error_message.clone()));
+             .returning(move |_| Err(Error::Other("Database connection failed".to_string())));
 
          let (log_state, _log_path) = create_test_log_state();
          let app_state = AppState {
              db_repo: Arc::new(mock_repo),
              log_state,
+             rez_toolchain: Mutex::new(None),
          };
 
          let result = app_state.db_repo.find_package_collections_by_uri(uri).await;
 
          assert!(result.is_err());
-         assert_eq!(result.err().unwrap(), "Database connection failed");
+         assert_eq!(result.err().unwrap().to_string(), "Database connection failed");
 
          let _ = fs::remove_file(_log_path);
      }
@@ -980,6 +835,7 @@ mod tests {
         let app_state = AppState {
             db_repo: Arc::new(mock_repo),
             log_state,
+            rez_toolchain: Mutex::new(None),
         };
 
         let result = app_state.db_repo.find_all_package_collections().await;
@@ -1007,6 +863,7 @@ mod tests {
          let app_state = AppState {
              db_repo: Arc::new(mock_repo),
              log_state,
+             rez_toolchain: Mutex::new(None),
          };
 
          let result = app_state.db_repo.find_all_package_collections().await;
@@ -1035,6 +892,7 @@ mod tests {
         let app_state = AppState {
             db_repo: Arc::new(mock_repo),
             log_state,
+            rez_toolchain: Mutex::new(None),
         };
 
         let result = app_state.db_repo.insert_package_collection(pkg_to_save).await;
@@ -1058,6 +916,7 @@ mod tests {
         let app_state = AppState {
             db_repo: Arc::new(mock_repo),
             log_state,
+            rez_toolchain: Mutex::new(None),
         };
 
         let result = app_state.db_repo.find_distinct_stage_names().await;
@@ -1088,6 +947,7 @@ mod tests {
         let app_state = AppState {
             db_repo: Arc::new(mock_repo),
             log_state,
+            rez_toolchain: Mutex::new(None),
         };
 
         let result = app_state.db_repo.find_distinct_stage_names().await;
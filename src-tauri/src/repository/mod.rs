@@ -0,0 +1,78 @@
+pub mod mongo;
+pub mod sqlite;
+
+use async_trait::async_trait;
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::error::Error;
+use crate::LogState;
+
+/// Storage abstraction over `package_collections` and `stages`. Every
+/// backend (MongoDB, the embedded offline store) implements this so the
+/// rest of the app never depends on which one is active.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait DbRepository: Send + Sync {
+    async fn find_package_collections_by_uri(&self, uri: &str) -> Result<Vec<PackageCollection>, Error>;
+    async fn find_all_package_collections(&self) -> Result<Vec<PackageCollection>, Error>;
+    async fn insert_package_collection(&self, package_data: PackageCollection) -> Result<(), Error>;
+    async fn find_package_collection_tools(&self, version: &str, uri: &str) -> Result<Option<Vec<String>>, Error>;
+    async fn find_stages_by_uri(&self, uri: &str, active_only: Option<bool>) -> Result<Vec<Stage>, Error>;
+    async fn insert_stage(&self, stage_data: Stage) -> Result<(), Error>;
+    async fn update_stages_active_status(&self, name: &str, uri: &str, active: bool) -> Result<(), Error>;
+    async fn update_stage_active_status_by_id(&self, id: ObjectId, active: bool) -> Result<(), Error>;
+    async fn find_stage_by_id(&self, id: ObjectId) -> Result<Option<Stage>, Error>;
+    async fn find_stage_history(&self, stage_name: &str, uri: &str) -> Result<Vec<Stage>, Error>;
+    async fn find_distinct_stage_names(&self) -> Result<Vec<String>, Error>;
+    /// Cheap connectivity check used by `test_mongodb_connection` and at
+    /// startup; must not require any prior write.
+    async fn ping(&self) -> Result<(), Error>;
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PackageCollection {
+    pub version: String,
+    pub packages: Vec<String>,
+    pub herit: String,
+    pub tools: Vec<String>,
+    pub created_at: String,
+    pub created_by: String,
+    pub uri: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Stage {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub name: String,
+    pub uri: String,
+    pub from_version: String,
+    pub rxt: String,
+    pub tools: Vec<String>,
+    pub created_at: String,
+    pub created_by: String,
+    pub active: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PackageCollectionResult {
+    pub success: bool,
+    pub message: Option<String>,
+    pub collections: Option<Vec<PackageCollection>>,
+}
+
+/// Builds the repository backend selected by the connection-string scheme,
+/// so switching backends is a single URI change:
+/// - `mongodb://` / `mongodb+srv://` → the live MongoDB backend
+/// - `sqlite://path` / `file://path` → the embedded offline backend
+pub async fn build_repository(uri: &str, log_state: LogState) -> Result<Arc<dyn DbRepository>, Error> {
+    if uri.starts_with("mongodb://") || uri.starts_with("mongodb+srv://") {
+        Ok(Arc::new(mongo::MongoDbRepository::connect(uri, log_state).await?))
+    } else if let Some(path) = uri.strip_prefix("sqlite://").or_else(|| uri.strip_prefix("file://")) {
+        Ok(Arc::new(sqlite::SqliteRepository::open(path, log_state)?))
+    } else {
+        Err(Error::Other(format!("Unsupported repository URI scheme: {}", uri)))
+    }
+}
@@ -0,0 +1,173 @@
+use async_trait::async_trait;
+use futures::stream::StreamExt;
+use mongodb::bson::{doc, oid::ObjectId, Bson};
+use mongodb::{options::ClientOptions, Client, Collection, Database};
+use serde::de::DeserializeOwned;
+
+use crate::error::Error;
+use crate::repository::{DbRepository, PackageCollection, Stage};
+use crate::{log_message, LogState};
+
+const DB_NAME: &str = "rez_launcher";
+
+pub struct MongoDbRepository {
+    db: Database,
+    log_state: LogState,
+}
+
+impl MongoDbRepository {
+    /// Parses `uri` and creates the driver client. The MongoDB driver
+    /// connects lazily, so this opens no server connection (a
+    /// `mongodb+srv://` URI still needs a DNS lookup to parse); use
+    /// `ping()` to actually verify connectivity.
+    pub async fn connect(uri: &str, log_state: LogState) -> Result<Self, Error> {
+        let options = ClientOptions::parse(uri).await
+            .map_err(|e| Error::InvalidUri(e.to_string()))?;
+        let client = Client::with_options(options)?;
+        let db = client.database(DB_NAME);
+        Ok(MongoDbRepository { db, log_state })
+    }
+
+    fn get_collection<T>(&self, name: &str) -> Collection<T> {
+        self.db.collection::<T>(name)
+    }
+
+    async fn fetch_documents_internal<T>(
+        &self,
+        collection_name: &str,
+        filter: impl Into<Option<mongodb::bson::Document>>,
+        log_msg_prefix: &str,
+    ) -> Result<Vec<T>, Error>
+    where
+        T: DeserializeOwned + Send + Sync + Unpin + Clone + std::fmt::Debug,
+    {
+        let collection = self.get_collection::<T>(collection_name);
+        let mut cursor = collection.find(filter, None).await?;
+
+        let mut documents = Vec::new();
+        while let Some(result) = cursor.next().await {
+            match result {
+                Ok(document) => documents.push(document),
+                Err(e) => log_message(&self.log_state, format!("Error fetching document: {}", e)),
+            }
+        }
+
+        #[cfg(debug_assertions)]
+        {
+            let doc_count = documents.len();
+            log_message(&self.log_state, format!("{}: {} documents retrieved.", log_msg_prefix, doc_count));
+        }
+        #[cfg(not(debug_assertions))]
+        log_message(&self.log_state, format!("{}: {}", log_msg_prefix, documents.len()));
+
+        Ok(documents)
+    }
+}
+
+#[async_trait]
+impl DbRepository for MongoDbRepository {
+    async fn find_package_collections_by_uri(&self, uri: &str) -> Result<Vec<PackageCollection>, Error> {
+        let filter = doc! { "uri": uri };
+        self.fetch_documents_internal(
+            "package_collections",
+            filter,
+            &format!("Retrieved package collections with URI: {}", uri)
+        ).await
+    }
+
+    async fn find_all_package_collections(&self) -> Result<Vec<PackageCollection>, Error> {
+        self.fetch_documents_internal(
+            "package_collections",
+            None,
+            "Retrieved all package collections"
+        ).await
+    }
+
+    async fn insert_package_collection(&self, package_data: PackageCollection) -> Result<(), Error> {
+        let collection = self.get_collection::<PackageCollection>("package_collections");
+        collection.insert_one(package_data, None).await?;
+        Ok(())
+    }
+
+    async fn find_package_collection_tools(&self, version: &str, uri: &str) -> Result<Option<Vec<String>>, Error> {
+        let collection = self.get_collection::<PackageCollection>("package_collections");
+        let filter = doc! { "version": version, "uri": uri };
+        match collection.find_one(filter, None).await? {
+            Some(package) => Ok(Some(package.tools)),
+            None => Ok(None),
+        }
+    }
+
+    async fn find_stages_by_uri(&self, uri: &str, active_only: Option<bool>) -> Result<Vec<Stage>, Error> {
+        let mut filter = doc! { "uri": uri };
+        if let Some(true) = active_only {
+            filter.insert("active", true);
+        }
+        let filter_status = if active_only.unwrap_or(false) { "active " } else { "" };
+        let log_msg = format!("Retrieved {}stages with URI: {}", filter_status, uri);
+        self.fetch_documents_internal("stages", filter, &log_msg).await
+    }
+
+    async fn insert_stage(&self, stage_data: Stage) -> Result<(), Error> {
+        let collection = self.get_collection::<Stage>("stages");
+        collection.insert_one(stage_data, None).await?;
+        Ok(())
+    }
+
+    async fn update_stages_active_status(&self, name: &str, uri: &str, active: bool) -> Result<(), Error> {
+        let collection = self.get_collection::<Stage>("stages");
+        let filter = doc! { "name": name, "uri": uri };
+        let update = doc! { "$set": { "active": active } };
+        collection.update_many(filter, update, None).await?;
+        Ok(())
+    }
+
+    async fn update_stage_active_status_by_id(&self, id: ObjectId, active: bool) -> Result<(), Error> {
+        let collection = self.get_collection::<Stage>("stages");
+        let filter = doc! { "_id": id };
+        let update = doc! { "$set": { "active": active } };
+        collection.update_one(filter, update, None).await?;
+        Ok(())
+    }
+
+    async fn find_stage_by_id(&self, id: ObjectId) -> Result<Option<Stage>, Error> {
+        let collection = self.get_collection::<Stage>("stages");
+        let filter = doc! { "_id": id };
+        Ok(collection.find_one(filter, None).await?)
+    }
+
+    async fn find_stage_history(&self, stage_name: &str, uri: &str) -> Result<Vec<Stage>, Error> {
+        let filter = doc! { "name": stage_name, "uri": uri };
+        let log_msg = format!("Retrieved stage versions for '{}' with URI '{}'", stage_name, uri);
+        self.fetch_documents_internal("stages", filter, &log_msg).await
+    }
+
+    async fn find_distinct_stage_names(&self) -> Result<Vec<String>, Error> {
+        let collection = self.get_collection::<Stage>("stages");
+        log_message(&self.log_state, "Fetching all unique stage names".to_string());
+        match collection.distinct("name", None, None).await {
+            Ok(names_bson) => {
+                let names: Vec<String> = names_bson.into_iter()
+                    .filter_map(|bson| match bson {
+                        Bson::String(s) => Some(s),
+                        _ => {
+                            log_message(&self.log_state, format!("Non-string value found in distinct stage names: {:?}", bson));
+                            None
+                        }
+                    })
+                    .collect();
+                log_message(&self.log_state, format!("Retrieved {} unique stage names", names.len()));
+                Ok(names)
+            }
+            Err(e) => {
+                log_message(&self.log_state, format!("Error fetching distinct stage names: {}", e));
+                Err(Error::Db(e))
+            }
+        }
+    }
+
+    async fn ping(&self) -> Result<(), Error> {
+        self.db.run_command(doc! {"ping": 1}, None).await?;
+        Ok(())
+    }
+}
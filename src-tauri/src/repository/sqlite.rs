@@ -0,0 +1,360 @@
+use async_trait::async_trait;
+use mongodb::bson::oid::ObjectId;
+use rusqlite::{params, Connection, Row};
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use crate::error::Error;
+use crate::repository::{DbRepository, PackageCollection, Stage};
+use crate::{log_message, LogState};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS package_collections (
+    version TEXT NOT NULL,
+    uri TEXT NOT NULL,
+    packages TEXT NOT NULL,
+    herit TEXT NOT NULL,
+    tools TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    created_by TEXT NOT NULL,
+    PRIMARY KEY (version, uri)
+);
+CREATE TABLE IF NOT EXISTS stages (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    uri TEXT NOT NULL,
+    from_version TEXT NOT NULL,
+    rxt TEXT NOT NULL,
+    tools TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    created_by TEXT NOT NULL,
+    active INTEGER NOT NULL
+);
+";
+
+fn sql_err(e: rusqlite::Error) -> Error {
+    Error::Other(format!("sqlite error: {}", e))
+}
+
+fn to_json(values: &[String]) -> String {
+    serde_json::to_string(values).unwrap_or_else(|_| "[]".to_string())
+}
+
+fn from_json(text: &str) -> Vec<String> {
+    serde_json::from_str(text).unwrap_or_default()
+}
+
+fn row_to_package_collection(row: &Row) -> rusqlite::Result<PackageCollection> {
+    Ok(PackageCollection {
+        version: row.get("version")?,
+        uri: row.get("uri")?,
+        packages: from_json(&row.get::<_, String>("packages")?),
+        herit: row.get("herit")?,
+        tools: from_json(&row.get::<_, String>("tools")?),
+        created_at: row.get("created_at")?,
+        created_by: row.get("created_by")?,
+    })
+}
+
+fn row_to_stage(row: &Row) -> rusqlite::Result<Stage> {
+    let id_hex: String = row.get("id")?;
+    Ok(Stage {
+        id: ObjectId::from_str(&id_hex).ok(),
+        name: row.get("name")?,
+        uri: row.get("uri")?,
+        from_version: row.get("from_version")?,
+        rxt: row.get("rxt")?,
+        tools: from_json(&row.get::<_, String>("tools")?),
+        created_at: row.get("created_at")?,
+        created_by: row.get("created_by")?,
+        active: row.get::<_, i64>("active")? != 0,
+    })
+}
+
+/// Embedded offline backend over a local SQLite file, so artists can
+/// browse/save collections and stages without a live MongoDB. Mirrors the
+/// `package_collections`/`stages` shape the Mongo backend uses.
+pub struct SqliteRepository {
+    conn: Mutex<Connection>,
+    log_state: LogState,
+}
+
+impl SqliteRepository {
+    pub fn open(path: &str, log_state: LogState) -> Result<Self, Error> {
+        let conn = Connection::open(path).map_err(sql_err)?;
+        conn.execute_batch(SCHEMA).map_err(sql_err)?;
+        Ok(SqliteRepository { conn: Mutex::new(conn), log_state })
+    }
+}
+
+#[async_trait]
+impl DbRepository for SqliteRepository {
+    async fn find_package_collections_by_uri(&self, uri: &str) -> Result<Vec<PackageCollection>, Error> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT * FROM package_collections WHERE uri = ?1")
+            .map_err(sql_err)?;
+        let collections = stmt
+            .query_map(params![uri], row_to_package_collection)
+            .map_err(sql_err)?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(sql_err)?;
+        log_message(&self.log_state, format!("Retrieved package collections with URI: {}", uri));
+        Ok(collections)
+    }
+
+    async fn find_all_package_collections(&self) -> Result<Vec<PackageCollection>, Error> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT * FROM package_collections").map_err(sql_err)?;
+        let collections = stmt
+            .query_map([], row_to_package_collection)
+            .map_err(sql_err)?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(sql_err)?;
+        log_message(&self.log_state, "Retrieved all package collections".to_string());
+        Ok(collections)
+    }
+
+    async fn insert_package_collection(&self, package_data: PackageCollection) -> Result<(), Error> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO package_collections (version, uri, packages, herit, tools, created_at, created_by)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+             ON CONFLICT(version, uri) DO UPDATE SET
+                packages = excluded.packages, herit = excluded.herit, tools = excluded.tools,
+                created_at = excluded.created_at, created_by = excluded.created_by",
+            params![
+                package_data.version,
+                package_data.uri,
+                to_json(&package_data.packages),
+                package_data.herit,
+                to_json(&package_data.tools),
+                package_data.created_at,
+                package_data.created_by,
+            ],
+        ).map_err(sql_err)?;
+        Ok(())
+    }
+
+    async fn find_package_collection_tools(&self, version: &str, uri: &str) -> Result<Option<Vec<String>>, Error> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT tools FROM package_collections WHERE version = ?1 AND uri = ?2")
+            .map_err(sql_err)?;
+        let tools = stmt
+            .query_row(params![version, uri], |row| row.get::<_, String>("tools"))
+            .map(|text| from_json(&text));
+        match tools {
+            Ok(tools) => Ok(Some(tools)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(sql_err(e)),
+        }
+    }
+
+    async fn find_stages_by_uri(&self, uri: &str, active_only: Option<bool>) -> Result<Vec<Stage>, Error> {
+        let conn = self.conn.lock().unwrap();
+        let stages = if active_only.unwrap_or(false) {
+            let mut stmt = conn
+                .prepare("SELECT * FROM stages WHERE uri = ?1 AND active = 1")
+                .map_err(sql_err)?;
+            let rows = stmt.query_map(params![uri], row_to_stage).map_err(sql_err)?
+                .collect::<rusqlite::Result<Vec<_>>>().map_err(sql_err)?;
+            rows
+        } else {
+            let mut stmt = conn
+                .prepare("SELECT * FROM stages WHERE uri = ?1")
+                .map_err(sql_err)?;
+            let rows = stmt.query_map(params![uri], row_to_stage).map_err(sql_err)?
+                .collect::<rusqlite::Result<Vec<_>>>().map_err(sql_err)?;
+            rows
+        };
+        log_message(&self.log_state, format!("Retrieved stages with URI: {}", uri));
+        Ok(stages)
+    }
+
+    async fn insert_stage(&self, stage_data: Stage) -> Result<(), Error> {
+        // `ObjectId::default()` is the all-zero id, not a fresh one, so
+        // clippy's unwrap_or_default suggestion would make every stage
+        // inserted without an explicit id collide on the `id` primary key.
+        #[allow(clippy::unwrap_or_default)]
+        let id = stage_data.id.unwrap_or_else(ObjectId::new);
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO stages (id, name, uri, from_version, rxt, tools, created_at, created_by, active)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                id.to_hex(),
+                stage_data.name,
+                stage_data.uri,
+                stage_data.from_version,
+                stage_data.rxt,
+                to_json(&stage_data.tools),
+                stage_data.created_at,
+                stage_data.created_by,
+                stage_data.active as i64,
+            ],
+        ).map_err(sql_err)?;
+        Ok(())
+    }
+
+    async fn update_stages_active_status(&self, name: &str, uri: &str, active: bool) -> Result<(), Error> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE stages SET active = ?1 WHERE name = ?2 AND uri = ?3",
+            params![active as i64, name, uri],
+        ).map_err(sql_err)?;
+        Ok(())
+    }
+
+    async fn update_stage_active_status_by_id(&self, id: ObjectId, active: bool) -> Result<(), Error> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE stages SET active = ?1 WHERE id = ?2",
+            params![active as i64, id.to_hex()],
+        ).map_err(sql_err)?;
+        Ok(())
+    }
+
+    async fn find_stage_by_id(&self, id: ObjectId) -> Result<Option<Stage>, Error> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT * FROM stages WHERE id = ?1").map_err(sql_err)?;
+        match stmt.query_row(params![id.to_hex()], row_to_stage) {
+            Ok(stage) => Ok(Some(stage)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(sql_err(e)),
+        }
+    }
+
+    async fn find_stage_history(&self, stage_name: &str, uri: &str) -> Result<Vec<Stage>, Error> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT * FROM stages WHERE name = ?1 AND uri = ?2")
+            .map_err(sql_err)?;
+        let stages = stmt
+            .query_map(params![stage_name, uri], row_to_stage)
+            .map_err(sql_err)?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(sql_err)?;
+        log_message(&self.log_state, format!("Retrieved stage versions for '{}' with URI '{}'", stage_name, uri));
+        Ok(stages)
+    }
+
+    async fn find_distinct_stage_names(&self) -> Result<Vec<String>, Error> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT DISTINCT name FROM stages").map_err(sql_err)?;
+        let names = stmt
+            .query_map([], |row| row.get::<_, String>("name"))
+            .map_err(sql_err)?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(sql_err)?;
+        log_message(&self.log_state, format!("Retrieved {} unique stage names", names.len()));
+        Ok(names)
+    }
+
+    async fn ping(&self) -> Result<(), Error> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute_batch("SELECT 1;").map_err(sql_err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::OpenOptions;
+
+    fn test_log_state() -> LogState {
+        let path = std::env::temp_dir().join(format!("rezlauncher_sqlite_test_{}.log", ObjectId::new().to_hex()));
+        let file = OpenOptions::new().create(true).append(true).open(path).unwrap();
+        LogState(Mutex::new(file))
+    }
+
+    fn dummy_package_collection(version: &str, uri: &str) -> PackageCollection {
+        PackageCollection {
+            version: version.to_string(),
+            packages: vec!["pkg1".to_string(), "pkg2".to_string()],
+            herit: "parent".to_string(),
+            tools: vec!["toolA".to_string(), "toolB".to_string()],
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            created_by: "test_user".to_string(),
+            uri: uri.to_string(),
+        }
+    }
+
+    fn dummy_stage(name: &str, uri: &str, active: bool) -> Stage {
+        Stage {
+            id: None,
+            name: name.to_string(),
+            uri: uri.to_string(),
+            from_version: "1.0.0".to_string(),
+            rxt: "resolve: pkg-1.0.0".to_string(),
+            tools: vec!["toolA".to_string()],
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            created_by: "test_user".to_string(),
+            active,
+        }
+    }
+
+    #[tokio::test]
+    async fn insert_and_find_package_collection_roundtrip() {
+        let repo = SqliteRepository::open(":memory:", test_log_state()).unwrap();
+        repo.insert_package_collection(dummy_package_collection("1.0.0", "test/uri")).await.unwrap();
+
+        let found = repo.find_package_collections_by_uri("test/uri").await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].version, "1.0.0");
+        assert_eq!(found[0].tools, vec!["toolA".to_string(), "toolB".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn insert_package_collection_upserts_on_conflict() {
+        let repo = SqliteRepository::open(":memory:", test_log_state()).unwrap();
+        repo.insert_package_collection(dummy_package_collection("1.0.0", "test/uri")).await.unwrap();
+
+        let mut updated = dummy_package_collection("1.0.0", "test/uri");
+        updated.tools = vec!["toolC".to_string()];
+        repo.insert_package_collection(updated).await.unwrap();
+
+        let found = repo.find_package_collections_by_uri("test/uri").await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].tools, vec!["toolC".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn find_package_collection_tools_missing_returns_none() {
+        let repo = SqliteRepository::open(":memory:", test_log_state()).unwrap();
+        let tools = repo.find_package_collection_tools("1.0.0", "missing/uri").await.unwrap();
+        assert_eq!(tools, None);
+    }
+
+    #[tokio::test]
+    async fn insert_stage_and_update_active_status() {
+        let repo = SqliteRepository::open(":memory:", test_log_state()).unwrap();
+        repo.insert_stage(dummy_stage("dev", "test/uri", true)).await.unwrap();
+        repo.insert_stage(dummy_stage("dev", "test/uri", true)).await.unwrap();
+
+        let active = repo.find_stages_by_uri("test/uri", Some(true)).await.unwrap();
+        assert_eq!(active.len(), 2);
+
+        repo.update_stages_active_status("dev", "test/uri", false).await.unwrap();
+        let active = repo.find_stages_by_uri("test/uri", Some(true)).await.unwrap();
+        assert!(active.is_empty());
+    }
+
+    #[tokio::test]
+    async fn find_distinct_stage_names_deduplicates() {
+        let repo = SqliteRepository::open(":memory:", test_log_state()).unwrap();
+        repo.insert_stage(dummy_stage("dev", "test/uri", true)).await.unwrap();
+        repo.insert_stage(dummy_stage("dev", "test/uri", false)).await.unwrap();
+        repo.insert_stage(dummy_stage("prod", "test/uri", true)).await.unwrap();
+
+        let mut names = repo.find_distinct_stage_names().await.unwrap();
+        names.sort();
+        assert_eq!(names, vec!["dev".to_string(), "prod".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn ping_succeeds_on_a_fresh_database() {
+        let repo = SqliteRepository::open(":memory:", test_log_state()).unwrap();
+        assert!(repo.ping().await.is_ok());
+    }
+}
@@ -0,0 +1,152 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::error::Error;
+use crate::logged_command::LoggedCommand;
+
+/// Env var that overrides `MIN_REZ_VERSION` without recompiling, e.g.
+/// "2.98.0". Consulted by `min_rez_version()`.
+pub const MIN_REZ_VERSION_ENV_VAR: &str = "REZLAUNCHER_MIN_REZ_VERSION";
+
+/// Minimum `rez` version this app is tested against. Studios pinned to an
+/// older rez release can lower this via `MIN_REZ_VERSION_ENV_VAR` instead of
+/// recompiling; see `min_rez_version()`.
+pub const MIN_REZ_VERSION: (u32, u32, u32) = (2, 100, 0);
+
+/// Resolves the minimum rez version to enforce: `MIN_REZ_VERSION_ENV_VAR` if
+/// set and parseable, otherwise the `MIN_REZ_VERSION` default.
+pub fn min_rez_version() -> (u32, u32, u32) {
+    std::env::var(MIN_REZ_VERSION_ENV_VAR)
+        .ok()
+        .and_then(|text| parse_version(&text))
+        .unwrap_or(MIN_REZ_VERSION)
+}
+
+/// Result of probing the system for a working `rez` installation: the
+/// resolved absolute path (so callers can invoke it directly instead of
+/// relying on `PATH` inside a spawned shell) and its parsed version.
+#[derive(Debug, Clone, Serialize)]
+pub struct RezToolchain {
+    pub executable_path: PathBuf,
+    pub version: (u32, u32, u32),
+}
+
+impl RezToolchain {
+    /// Runs `rez --version`, resolves the absolute path of the `rez` on
+    /// `PATH`, and checks the parsed version against `min_version`.
+    /// Returns a structured, user-actionable error instead of letting a
+    /// raw spawn/parse failure bubble up to the first caller that happens
+    /// to shell out to `rez`.
+    pub fn detect(min_version: (u32, u32, u32)) -> Result<Self, Error> {
+        let version_result = LoggedCommand::shell("rez --version", "rez_toolchain_detect")
+            .run()
+            .map_err(|_| Error::Other(
+                "Could not run `rez --version`. Is rez installed and on PATH?".to_string()
+            ))?;
+
+        let version_text = if version_result.stdout.trim().is_empty() {
+            version_result.stderr.trim()
+        } else {
+            version_result.stdout.trim()
+        };
+        let version = parse_version(version_text).ok_or_else(|| Error::Other(format!(
+            "Could not parse a rez version from `rez --version` output: '{}'",
+            version_text
+        )))?;
+
+        let which_command = if cfg!(target_os = "windows") { "where rez" } else { "command -v rez" };
+        let which_result = LoggedCommand::shell(which_command, "rez_toolchain_which").run()
+            .map_err(|_| Error::Other("rez --version succeeded but its location could not be resolved".to_string()))?;
+        let executable_path = PathBuf::from(
+            which_result.stdout.lines().next().unwrap_or_default().trim()
+        );
+        if executable_path.as_os_str().is_empty() {
+            return Err(Error::Other("Could not resolve the absolute path of rez".to_string()));
+        }
+
+        if version < min_version {
+            return Err(Error::Other(format!(
+                "Found rez {}.{}.{} at {}, but rezlauncher requires at least {}.{}.{}. Please upgrade rez.",
+                version.0, version.1, version.2, executable_path.display(),
+                min_version.0, min_version.1, min_version.2
+            )));
+        }
+
+        Ok(RezToolchain { executable_path, version })
+    }
+
+    /// Starts a `LoggedCommand` invoking the resolved `rez` path directly,
+    /// rather than a bare `rez` that depends on the child's `PATH`.
+    pub fn command(&self, label: &str) -> LoggedCommand {
+        LoggedCommand::new(&self.executable_path.to_string_lossy(), label)
+    }
+}
+
+fn parse_version(text: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = text.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().unwrap_or("0").parse().ok()?;
+    let patch_text = parts.next().unwrap_or("0");
+    let patch_digits: String = patch_text.chars().take_while(|c| c.is_ascii_digit()).collect();
+    let patch = if patch_digits.is_empty() { 0 } else { patch_digits.parse().ok()? };
+    Some((major, minor, patch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_plain_semver() {
+        assert_eq!(parse_version("2.114.0"), Some((2, 114, 0)));
+    }
+
+    #[test]
+    fn parses_version_missing_minor_and_patch() {
+        assert_eq!(parse_version("2"), Some((2, 0, 0)));
+    }
+
+    #[test]
+    fn parses_typical_rez_version_output() {
+        assert_eq!(parse_version("2.114.1\n"), Some((2, 114, 1)));
+    }
+
+    #[test]
+    fn parses_patch_with_trailing_non_numeric_suffix() {
+        assert_eq!(parse_version("2.114.1-beta"), Some((2, 114, 1)));
+    }
+
+    #[test]
+    fn rejects_non_numeric_major() {
+        assert_eq!(parse_version("rez-not-installed"), None);
+    }
+
+    // `min_rez_version()` reads a process-global env var, but the default
+    // test harness runs tests in the same process in parallel; without
+    // this guard the two env-var tests below could interleave their
+    // set_var/remove_var calls and read each other's value.
+    static MIN_REZ_VERSION_ENV_VAR_GUARD: once_cell::sync::Lazy<std::sync::Mutex<()>> =
+        once_cell::sync::Lazy::new(|| std::sync::Mutex::new(()));
+
+    #[test]
+    fn min_rez_version_falls_back_to_the_default_when_unset() {
+        let _guard = MIN_REZ_VERSION_ENV_VAR_GUARD.lock().unwrap();
+        std::env::remove_var(MIN_REZ_VERSION_ENV_VAR);
+        assert_eq!(min_rez_version(), MIN_REZ_VERSION);
+    }
+
+    #[test]
+    fn min_rez_version_honours_the_env_var_override() {
+        let _guard = MIN_REZ_VERSION_ENV_VAR_GUARD.lock().unwrap();
+        std::env::set_var(MIN_REZ_VERSION_ENV_VAR, "2.98.0");
+        assert_eq!(min_rez_version(), (2, 98, 0));
+        std::env::remove_var(MIN_REZ_VERSION_ENV_VAR);
+    }
+
+    #[test]
+    fn version_ordering_respects_min_version_check() {
+        assert!(parse_version("1.99.9").unwrap() < MIN_REZ_VERSION);
+        assert!(parse_version("2.100.0").unwrap() >= MIN_REZ_VERSION);
+    }
+}
@@ -0,0 +1,213 @@
+use std::collections::{BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::repository::Stage;
+
+/// One version pulled from the history of a named stage: the package
+/// collection version it was built from, plus who/when saved it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StageVersionInfo {
+    pub version: String,
+    pub created_by: String,
+    pub created_at: String,
+}
+
+impl From<&Stage> for StageVersionInfo {
+    fn from(stage: &Stage) -> Self {
+        StageVersionInfo {
+            version: stage.from_version.clone(),
+            created_by: stage.created_by.clone(),
+            created_at: stage.created_at.clone(),
+        }
+    }
+}
+
+/// A package present in both stages, resolved to a different version.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PackageVersionChange {
+    pub name: String,
+    pub from_version: String,
+    pub to_version: String,
+}
+
+/// Audit trail for a stage revert/promotion decision: what changed between
+/// two saved versions of the same named stage, so the UI can render a diff
+/// view instead of the user having to diff raw RXT text by hand.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StageDiffReport {
+    pub from: StageVersionInfo,
+    pub to: StageVersionInfo,
+    pub tools_added: Vec<String>,
+    pub tools_removed: Vec<String>,
+    pub packages_added: Vec<String>,
+    pub packages_removed: Vec<String>,
+    pub packages_version_changed: Vec<PackageVersionChange>,
+}
+
+/// Extracts `name -> version` pairs from the resolved-package lines of a
+/// stored RXT file. `rez env -o` writes one resolved package per whitespace
+/// token as `name-version` (alongside header/footer lines and unversioned
+/// `platform`/`arch` tokens that simply won't match), so this only needs to
+/// recognise that token shape.
+fn parse_resolved_packages(rxt: &str) -> HashMap<String, String> {
+    let mut packages = HashMap::new();
+    for line in rxt.lines() {
+        for token in line.split_whitespace() {
+            if let Some((name, version)) = split_name_version(token) {
+                packages.insert(name, version);
+            }
+        }
+    }
+    packages
+}
+
+/// Splits a `name-version` token at the last `-` immediately followed by a
+/// digit, e.g. `maya-2023.1` -> `("maya", "2023.1")`. Tokens with no such
+/// split (section headers, unversioned entries) are ignored.
+fn split_name_version(token: &str) -> Option<(String, String)> {
+    let bytes = token.as_bytes();
+    for i in (0..token.len()).rev() {
+        if bytes[i] == b'-' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit) {
+            return Some((token[..i].to_string(), token[i + 1..].to_string()));
+        }
+    }
+    None
+}
+
+/// Computes the audit report between two saved versions of the same named
+/// stage: tool list additions/removals, plus package additions, removals
+/// and version changes parsed out of each stage's stored RXT.
+pub fn diff_stages(from: &Stage, to: &Stage) -> StageDiffReport {
+    let from_tools: BTreeSet<&String> = from.tools.iter().collect();
+    let to_tools: BTreeSet<&String> = to.tools.iter().collect();
+    let tools_added = to_tools.difference(&from_tools).map(|s| (*s).clone()).collect();
+    let tools_removed = from_tools.difference(&to_tools).map(|s| (*s).clone()).collect();
+
+    let from_packages = parse_resolved_packages(&from.rxt);
+    let to_packages = parse_resolved_packages(&to.rxt);
+
+    let mut packages_added: Vec<String> = to_packages.keys()
+        .filter(|name| !from_packages.contains_key(*name))
+        .cloned()
+        .collect();
+    packages_added.sort();
+
+    let mut packages_removed: Vec<String> = from_packages.keys()
+        .filter(|name| !to_packages.contains_key(*name))
+        .cloned()
+        .collect();
+    packages_removed.sort();
+
+    let mut packages_version_changed: Vec<PackageVersionChange> = from_packages.iter()
+        .filter_map(|(name, from_version)| {
+            to_packages.get(name)
+                .filter(|to_version| *to_version != from_version)
+                .map(|to_version| PackageVersionChange {
+                    name: name.clone(),
+                    from_version: from_version.clone(),
+                    to_version: to_version.clone(),
+                })
+        })
+        .collect();
+    packages_version_changed.sort_by(|a, b| a.name.cmp(&b.name));
+
+    StageDiffReport {
+        from: StageVersionInfo::from(from),
+        to: StageVersionInfo::from(to),
+        tools_added,
+        tools_removed,
+        packages_added,
+        packages_removed,
+        packages_version_changed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A realistic `rez env -o` resolve section: one `name-version` token per
+    // line, plus header/footer lines and unversioned platform/arch entries
+    // that must NOT be mistaken for packages.
+    const SAMPLE_RXT: &str = "\
+>>>
+Resolved packages:
+  platform-linux
+  arch-x86_64
+  python-3.9.7
+  maya-2023.1
+<<<
+";
+
+    fn dummy_stage(from_version: &str, rxt: &str, tools: &[&str]) -> Stage {
+        Stage {
+            id: None,
+            name: "dev".to_string(),
+            uri: "test/uri".to_string(),
+            from_version: from_version.to_string(),
+            rxt: rxt.to_string(),
+            tools: tools.iter().map(|s| s.to_string()).collect(),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            created_by: "test_user".to_string(),
+            active: true,
+        }
+    }
+
+    #[test]
+    fn splits_a_versioned_token() {
+        assert_eq!(split_name_version("maya-2023.1"), Some(("maya".to_string(), "2023.1".to_string())));
+    }
+
+    #[test]
+    fn ignores_unversioned_tokens() {
+        assert_eq!(split_name_version("platform-linux"), None);
+        assert_eq!(split_name_version("Resolved"), None);
+    }
+
+    #[test]
+    fn parses_resolved_packages_from_a_sample_rxt() {
+        let packages = parse_resolved_packages(SAMPLE_RXT);
+        assert_eq!(packages.len(), 2);
+        assert_eq!(packages.get("python"), Some(&"3.9.7".to_string()));
+        assert_eq!(packages.get("maya"), Some(&"2023.1".to_string()));
+        assert!(!packages.contains_key("platform"));
+        assert!(!packages.contains_key("arch"));
+    }
+
+    #[test]
+    fn diff_stages_reports_added_removed_and_version_changed_packages() {
+        let from_rxt = "\
+>>>
+Resolved packages:
+  platform-linux
+  python-3.9.7
+  maya-2023.1
+<<<
+";
+        let to_rxt = "\
+>>>
+Resolved packages:
+  platform-linux
+  python-3.10.2
+  nuke-14.0
+<<<
+";
+        let from = dummy_stage("1.0.0", from_rxt, &["maya_launcher"]);
+        let to = dummy_stage("1.1.0", to_rxt, &["maya_launcher", "nuke_launcher"]);
+
+        let report = diff_stages(&from, &to);
+
+        assert_eq!(report.from.version, "1.0.0");
+        assert_eq!(report.to.version, "1.1.0");
+        assert_eq!(report.tools_added, vec!["nuke_launcher".to_string()]);
+        assert!(report.tools_removed.is_empty());
+        assert_eq!(report.packages_added, vec!["nuke".to_string()]);
+        assert_eq!(report.packages_removed, vec!["maya".to_string()]);
+        assert_eq!(report.packages_version_changed, vec![PackageVersionChange {
+            name: "python".to_string(),
+            from_version: "3.9.7".to_string(),
+            to_version: "3.10.2".to_string(),
+        }]);
+    }
+}